@@ -8,12 +8,15 @@ use std::{cmp, fmt, io, str, usize};
 ///
 /// [`Decoder`]: crate::codec::Decoder
 /// [`Encoder`]: crate::codec::Encoder
-#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct ChunkCodec {
-    // the byte to use to end a chunk (message)
-    delimiter: u8,
+    // the byte(s) that end a chunk (message)
+    seek_delimiters: SeekDelimiters,
 
-    // Stored index of the next index to examine for a `\n` character.
+    // the byte to emit between chunks when encoding
+    write_delimiter: u8,
+
+    // Stored index of the next index to examine for a delimiter.
     // This is used to optimize searching.
     // For example, if `decode` was called with `abc`, it would hold `3`,
     // because that is the next index to examine.
@@ -22,7 +25,7 @@ pub struct ChunkCodec {
     next_index: usize,
 
     /// The maximum length for a given chunk. If `usize::MAX`, chunks will be
-    /// read until a `\n` character is reached.
+    /// read until a delimiter is reached.
     max_length: usize,
 
     /// Are we currently discarding the remainder of a chunk which was over
@@ -30,6 +33,26 @@ pub struct ChunkCodec {
     is_discarding: bool,
 }
 
+/// The set of bytes a [`ChunkCodec`] treats as a chunk boundary while decoding.
+///
+/// [`ChunkCodec`]: crate::codec::ChunkCodec
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+enum SeekDelimiters {
+    /// Split on a single, specific byte.
+    Single(u8),
+    /// Split on any byte found in the set.
+    Any(Vec<u8>),
+}
+
+impl SeekDelimiters {
+    fn contains(&self, byte: u8) -> bool {
+        match self {
+            SeekDelimiters::Single(delimiter) => byte == *delimiter,
+            SeekDelimiters::Any(set) => set.contains(&byte),
+        }
+    }
+}
+
 impl ChunkCodec {
     /// Returns a `ChunkCodec` for splitting up data into chunks.
     ///
@@ -42,7 +65,37 @@ impl ChunkCodec {
     /// [`new_with_max_length`]: crate::codec::ChunkCodec::new_with_max_length()
     pub fn new(delimiter: u8) -> ChunkCodec {
         ChunkCodec {
-            delimiter,
+            seek_delimiters: SeekDelimiters::Single(delimiter),
+            write_delimiter: delimiter,
+            next_index: 0,
+            max_length: usize::MAX,
+            is_discarding: false,
+        }
+    }
+
+    /// Returns a `ChunkCodec` for splitting up data into chunks wherever *any*
+    /// byte from `seek_delimiters` is found, instead of a single fixed byte.
+    ///
+    /// This is useful for upstream sources that terminate records with any of
+    /// several characters (e.g. `,`, `;`, `\r` or `\n` mixed in one stream). A
+    /// chunk between two adjacent delimiters decodes to an empty string rather
+    /// than being skipped.
+    ///
+    /// Encoding still emits a single delimiter byte between chunks, taken as
+    /// the first byte of `seek_delimiters` (or `\n` if the set is empty).
+    ///
+    /// # Note
+    ///
+    /// The returned `ChunkCodec` will not have an upper bound on the length
+    /// of a buffered chunk. See the documentation for [`new_with_max_length`]
+    /// for information on why this could be a potential security risk.
+    ///
+    /// [`new_with_max_length`]: crate::codec::ChunkCodec::new_with_max_length()
+    pub fn new_any(seek_delimiters: Vec<u8>) -> ChunkCodec {
+        let write_delimiter = seek_delimiters.first().copied().unwrap_or(b'\n');
+        ChunkCodec {
+            seek_delimiters: SeekDelimiters::Any(seek_delimiters),
+            write_delimiter,
             next_index: 0,
             max_length: usize::MAX,
             is_discarding: false,
@@ -74,6 +127,20 @@ impl ChunkCodec {
             ..ChunkCodec::new(delimiter)
         }
     }
+
+    /// Returns a [`new_any`] `ChunkCodec` with a maximum chunk length limit.
+    ///
+    /// See [`new_with_max_length`] for the semantics of `max_length`.
+    ///
+    /// [`new_any`]: crate::codec::ChunkCodec::new_any()
+    /// [`new_with_max_length`]: crate::codec::ChunkCodec::new_with_max_length()
+    #[allow(dead_code)]
+    pub fn new_any_with_max_length(seek_delimiters: Vec<u8>, max_length: usize) -> Self {
+        ChunkCodec {
+            max_length,
+            ..ChunkCodec::new_any(seek_delimiters)
+        }
+    }
 }
 
 fn utf8(buf: &[u8]) -> Result<&str, io::Error> {
@@ -93,7 +160,7 @@ impl Decoder for ChunkCodec {
 
             let newchunk_offset = buf[self.next_index..read_to]
                 .iter()
-                .position(|b| *b == self.delimiter);
+                .position(|b| self.seek_delimiters.contains(*b));
 
             match (self.is_discarding, newchunk_offset) {
                 (true, Some(offset)) => {
@@ -168,7 +235,7 @@ where
         let chunk = chunk.as_ref();
         buf.reserve(chunk.len() + 1);
         buf.put(chunk.as_bytes());
-        buf.put_u8(self.delimiter);
+        buf.put_u8(self.write_delimiter);
         Ok(())
     }
 }
@@ -184,6 +251,9 @@ impl Default for ChunkCodec {
 pub enum ChunkCodecError {
     /// The maximum chunk length was exceeded.
     MaxChunkLengthExceeded,
+    /// The frame did not follow the wire format the codec expected, e.g. an
+    /// invalid chunk-size line or a missing CRLF.
+    InvalidFrame(String),
     /// An IO error occured.
     Io(io::Error),
 }
@@ -192,6 +262,7 @@ impl fmt::Display for ChunkCodecError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ChunkCodecError::MaxChunkLengthExceeded => write!(f, "max chunk length exceeded"),
+            ChunkCodecError::InvalidFrame(reason) => write!(f, "invalid frame: {}", reason),
             ChunkCodecError::Io(e) => write!(f, "{}", e),
         }
     }
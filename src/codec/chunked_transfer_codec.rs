@@ -0,0 +1,318 @@
+use tokio_util::codec::Decoder;
+
+use bytes::{Buf, BytesMut};
+use std::cmp;
+
+use super::chunk_codec::ChunkCodecError;
+
+/// Where we are within the RFC 7230 chunked-transfer-encoding state machine.
+///
+/// Tracking state explicitly (rather than re-parsing from scratch) lets
+/// `decode` resume correctly when a chunk-size line, a chunk body, or a
+/// trailer arrives split across multiple TCP packets.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+enum State {
+    /// Reading hex digits (and ignoring any `;`-delimited chunk extension)
+    /// of the chunk-size line, up to the CR.
+    ReadSize,
+    /// The chunk-size line's CR has been seen; expecting its LF.
+    ReadSizeLf,
+    /// Reading the chunk body's payload bytes.
+    Body,
+    /// The chunk body has been fully read; expecting the trailing CR.
+    BodyCr,
+    /// The chunk body's CR has been seen; expecting its LF.
+    BodyLf,
+    /// The terminating zero-length chunk was seen; reading trailer header
+    /// lines up to the blank line that ends the message.
+    Trailer,
+    /// The message is complete; no further frames will be produced.
+    End,
+}
+
+/// A [`Decoder`] that reads a body framed with RFC 7230 chunked
+/// transfer-encoding and yields each decoded chunk's payload.
+///
+/// [`Decoder`]: crate::codec::Decoder
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ChunkedTransferCodec {
+    state: State,
+    // hex digits of the chunk-size line accumulated so far
+    size_digits: Vec<u8>,
+    // whether we're past the `;` that starts a chunk extension, and should
+    // ignore bytes until the CR
+    in_extension: bool,
+    // payload bytes still to be read for the chunk currently in `Body`
+    remaining: usize,
+    // payload bytes accumulated so far for the chunk currently in `Body`
+    current_chunk: Vec<u8>,
+    // length of the trailer header line read so far, and whether its last
+    // byte was a CR; used to detect the blank line that ends the trailers
+    trailer_line_len: usize,
+    trailer_cr: bool,
+    /// The maximum size a single chunk's advertised length may be. If
+    /// `usize::MAX`, chunks of any advertised size will be accepted.
+    max_chunk_length: usize,
+}
+
+impl ChunkedTransferCodec {
+    /// Returns a `ChunkedTransferCodec` ready to decode a new chunked body.
+    ///
+    /// # Note
+    ///
+    /// Setting a length limit via [`new_with_max_chunk_length`] is highly
+    /// recommended for any `ChunkedTransferCodec` exposed to untrusted
+    /// input, since a hostile chunk-size line could otherwise request an
+    /// unbounded amount of buffering.
+    ///
+    /// [`new_with_max_chunk_length`]: crate::codec::ChunkedTransferCodec::new_with_max_chunk_length()
+    pub fn new() -> Self {
+        ChunkedTransferCodec {
+            state: State::ReadSize,
+            size_digits: Vec::new(),
+            in_extension: false,
+            remaining: 0,
+            current_chunk: Vec::new(),
+            trailer_line_len: 0,
+            trailer_cr: false,
+            max_chunk_length: usize::MAX,
+        }
+    }
+
+    /// Returns a `ChunkedTransferCodec` that rejects any chunk whose
+    /// advertised size exceeds `max_chunk_length`, returning
+    /// [`ChunkCodecError::MaxChunkLengthExceeded`].
+    ///
+    /// [`ChunkCodecError::MaxChunkLengthExceeded`]: crate::codec::ChunkCodecError::MaxChunkLengthExceeded
+    pub fn new_with_max_chunk_length(max_chunk_length: usize) -> Self {
+        ChunkedTransferCodec {
+            max_chunk_length,
+            ..ChunkedTransferCodec::new()
+        }
+    }
+}
+
+impl Default for ChunkedTransferCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn invalid(reason: &str) -> ChunkCodecError {
+    ChunkCodecError::InvalidFrame(reason.to_string())
+}
+
+impl Decoder for ChunkedTransferCodec {
+    type Item = Vec<u8>;
+    type Error = ChunkCodecError;
+
+    fn decode(&mut self, buf: &mut BytesMut) -> Result<Option<Vec<u8>>, ChunkCodecError> {
+        loop {
+            match self.state {
+                State::ReadSize => {
+                    if buf.is_empty() {
+                        return Ok(None);
+                    }
+                    let byte = buf[0];
+                    buf.advance(1);
+                    match byte {
+                        b'\r' => self.state = State::ReadSizeLf,
+                        b';' => self.in_extension = true,
+                        _ if self.in_extension => {}
+                        b'0'..=b'9' | b'a'..=b'f' | b'A'..=b'F' => self.size_digits.push(byte),
+                        _ => return Err(invalid("non-hex byte in chunk-size line")),
+                    }
+                }
+                State::ReadSizeLf => {
+                    if buf.is_empty() {
+                        return Ok(None);
+                    }
+                    let byte = buf[0];
+                    buf.advance(1);
+                    if byte != b'\n' {
+                        return Err(invalid("chunk-size line missing LF"));
+                    }
+                    let size_str = std::str::from_utf8(&self.size_digits)
+                        .map_err(|_| invalid("chunk-size is not ASCII"))?;
+                    let size = usize::from_str_radix(size_str, 16)
+                        .map_err(|_| invalid("chunk-size is not valid hex"))?;
+                    self.size_digits.clear();
+                    self.in_extension = false;
+                    if size > self.max_chunk_length {
+                        return Err(ChunkCodecError::MaxChunkLengthExceeded);
+                    }
+                    if size == 0 {
+                        self.trailer_line_len = 0;
+                        self.trailer_cr = false;
+                        self.state = State::Trailer;
+                    } else {
+                        self.remaining = size;
+                        self.current_chunk = Vec::new();
+                        self.state = State::Body;
+                    }
+                }
+                State::Body => {
+                    if buf.is_empty() {
+                        return Ok(None);
+                    }
+                    let n = cmp::min(self.remaining, buf.len());
+                    self.current_chunk.extend_from_slice(&buf[..n]);
+                    buf.advance(n);
+                    self.remaining -= n;
+                    if self.remaining > 0 {
+                        return Ok(None);
+                    }
+                    self.state = State::BodyCr;
+                }
+                State::BodyCr => {
+                    if buf.is_empty() {
+                        return Ok(None);
+                    }
+                    let byte = buf[0];
+                    buf.advance(1);
+                    if byte != b'\r' {
+                        return Err(invalid("chunk body missing trailing CR"));
+                    }
+                    self.state = State::BodyLf;
+                }
+                State::BodyLf => {
+                    if buf.is_empty() {
+                        return Ok(None);
+                    }
+                    let byte = buf[0];
+                    buf.advance(1);
+                    if byte != b'\n' {
+                        return Err(invalid("chunk body missing trailing LF"));
+                    }
+                    self.state = State::ReadSize;
+                    return Ok(Some(std::mem::take(&mut self.current_chunk)));
+                }
+                State::Trailer => {
+                    if buf.is_empty() {
+                        return Ok(None);
+                    }
+                    let byte = buf[0];
+                    buf.advance(1);
+                    match byte {
+                        b'\r' => self.trailer_cr = true,
+                        b'\n' => {
+                            let blank_line = self.trailer_cr && self.trailer_line_len == 0;
+                            self.trailer_cr = false;
+                            self.trailer_line_len = 0;
+                            if blank_line {
+                                self.state = State::End;
+                            }
+                        }
+                        _ => {
+                            self.trailer_cr = false;
+                            self.trailer_line_len += 1;
+                        }
+                    }
+                }
+                State::End => return Ok(None),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_a_single_chunk_with_trailer() {
+        let mut codec = ChunkedTransferCodec::new();
+        let mut buf = BytesMut::from(&b"5\r\nhello\r\n0\r\n\r\n"[..]);
+
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(b"hello".to_vec()));
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn decodes_multiple_chunks() {
+        let mut codec = ChunkedTransferCodec::new();
+        let mut buf = BytesMut::from(&b"4\r\nWiki\r\n5\r\npedia\r\n0\r\n\r\n"[..]);
+
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(b"Wiki".to_vec()));
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(b"pedia".to_vec()));
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+    }
+
+    #[test]
+    fn ignores_chunk_extensions() {
+        let mut codec = ChunkedTransferCodec::new();
+        let mut buf = BytesMut::from(&b"5;ext=value\r\nhello\r\n0\r\n\r\n"[..]);
+
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(b"hello".to_vec()));
+    }
+
+    #[test]
+    fn consumes_trailer_headers_before_end() {
+        let mut codec = ChunkedTransferCodec::new();
+        let mut buf =
+            BytesMut::from(&b"5\r\nhello\r\n0\r\nX-Trailer: value\r\nX-Other: two\r\n\r\n"[..]);
+
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(b"hello".to_vec()));
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn resumes_across_split_reads_at_every_boundary() {
+        let whole = b"5\r\nhello\r\n0\r\n\r\n";
+        // Feed the stream one byte at a time to exercise every state
+        // transition landing on a packet boundary.
+        let mut codec = ChunkedTransferCodec::new();
+        let mut buf = BytesMut::new();
+        let mut result = None;
+        for &byte in whole {
+            buf.extend_from_slice(&[byte]);
+            if let Some(chunk) = codec.decode(&mut buf).unwrap() {
+                result = Some(chunk);
+            }
+        }
+        assert_eq!(result, Some(b"hello".to_vec()));
+    }
+
+    #[test]
+    fn rejects_non_hex_chunk_size() {
+        let mut codec = ChunkedTransferCodec::new();
+        let mut buf = BytesMut::from(&b"zz\r\nhello\r\n"[..]);
+
+        assert!(matches!(
+            codec.decode(&mut buf),
+            Err(ChunkCodecError::InvalidFrame(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_missing_crlf_after_body() {
+        let mut codec = ChunkedTransferCodec::new();
+        let mut buf = BytesMut::from(&b"5\r\nhelloXX"[..]);
+
+        assert!(matches!(
+            codec.decode(&mut buf),
+            Err(ChunkCodecError::InvalidFrame(_))
+        ));
+    }
+
+    #[test]
+    fn enforces_max_chunk_length() {
+        let mut codec = ChunkedTransferCodec::new_with_max_chunk_length(4);
+        let mut buf = BytesMut::from(&b"5\r\nhello\r\n"[..]);
+
+        assert!(matches!(
+            codec.decode(&mut buf),
+            Err(ChunkCodecError::MaxChunkLengthExceeded)
+        ));
+    }
+
+    #[test]
+    fn returns_none_for_an_incomplete_chunk() {
+        let mut codec = ChunkedTransferCodec::new();
+        let mut buf = BytesMut::from(&b"5\r\nhel"[..]);
+
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+    }
+}
@@ -0,0 +1,192 @@
+use tokio_util::codec::{Decoder, Encoder};
+
+use bytes::{Buf, BufMut, BytesMut};
+
+use super::chunk_codec::ChunkCodecError;
+
+/// A maximum of 10 varint bytes is enough to encode any `u64` length; a
+/// prefix that grows past this is either corrupt or hostile, so we bail out
+/// rather than keep scanning.
+const MAX_VARINT_BYTES: usize = 10;
+
+/// A [`Decoder`] and [`Encoder`] implementation that frames binary data with
+/// an unsigned LEB128 varint length prefix, instead of a delimiter byte.
+///
+/// This allows payloads that may legitimately contain any byte value
+/// (including whatever byte a [`ChunkCodec`] would otherwise treat as a
+/// delimiter) to be framed unambiguously.
+///
+/// [`Decoder`]: crate::codec::Decoder
+/// [`Encoder`]: crate::codec::Encoder
+/// [`ChunkCodec`]: crate::codec::ChunkCodec
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct LengthPrefixedCodec {
+    /// The maximum length for a given frame. If `usize::MAX`, frames of any
+    /// advertised length will be accepted.
+    max_length: usize,
+}
+
+impl LengthPrefixedCodec {
+    /// Returns a `LengthPrefixedCodec` with no upper bound on frame length.
+    ///
+    /// # Note
+    ///
+    /// Setting a length limit via [`new_with_max_length`] is highly
+    /// recommended for any `LengthPrefixedCodec` exposed to untrusted input,
+    /// since a garbage or hostile length prefix could otherwise request an
+    /// unbounded amount of buffering.
+    ///
+    /// [`new_with_max_length`]: crate::codec::LengthPrefixedCodec::new_with_max_length()
+    pub fn new() -> Self {
+        LengthPrefixedCodec {
+            max_length: usize::MAX,
+        }
+    }
+
+    /// Returns a `LengthPrefixedCodec` that rejects any frame whose decoded
+    /// length prefix exceeds `max_length`, returning
+    /// [`ChunkCodecError::MaxChunkLengthExceeded`].
+    ///
+    /// [`ChunkCodecError::MaxChunkLengthExceeded`]: crate::codec::ChunkCodecError::MaxChunkLengthExceeded
+    pub fn new_with_max_length(max_length: usize) -> Self {
+        LengthPrefixedCodec { max_length }
+    }
+}
+
+impl Default for LengthPrefixedCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads a varint prefix from the front of `buf` without consuming it.
+///
+/// Returns `Ok(None)` if `buf` does not yet hold a complete prefix (i.e. it
+/// ends on a byte with the continuation bit set, or is empty).
+fn read_varint_prefix(buf: &[u8]) -> Result<Option<(usize, u64)>, ChunkCodecError> {
+    let mut value: u64 = 0;
+    let mut shift = 0u32;
+
+    for (i, &byte) in buf.iter().enumerate() {
+        if i == MAX_VARINT_BYTES {
+            return Err(ChunkCodecError::MaxChunkLengthExceeded);
+        }
+        value |= u64::from(byte & 0x7F) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(Some((i + 1, value)));
+        }
+        shift += 7;
+    }
+
+    Ok(None)
+}
+
+impl Decoder for LengthPrefixedCodec {
+    type Item = Vec<u8>;
+    type Error = ChunkCodecError;
+
+    fn decode(&mut self, buf: &mut BytesMut) -> Result<Option<Vec<u8>>, ChunkCodecError> {
+        let (prefix_len, length) = match read_varint_prefix(buf)? {
+            Some(prefix) => prefix,
+            None => return Ok(None),
+        };
+
+        let length = length as usize;
+        if length > self.max_length {
+            return Err(ChunkCodecError::MaxChunkLengthExceeded);
+        }
+
+        if buf.len() < prefix_len + length {
+            return Ok(None);
+        }
+
+        buf.advance(prefix_len);
+        Ok(Some(buf.split_to(length).to_vec()))
+    }
+}
+
+impl Encoder<&[u8]> for LengthPrefixedCodec {
+    type Error = ChunkCodecError;
+
+    fn encode(&mut self, data: &[u8], buf: &mut BytesMut) -> Result<(), ChunkCodecError> {
+        let mut remaining = data.len() as u64;
+        buf.reserve(data.len() + MAX_VARINT_BYTES);
+        loop {
+            let byte = (remaining & 0x7F) as u8;
+            remaining >>= 7;
+            if remaining == 0 {
+                buf.put_u8(byte);
+                break;
+            }
+            buf.put_u8(byte | 0x80);
+        }
+        buf.put(data);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_via_encode_and_decode() {
+        let mut codec = LengthPrefixedCodec::new();
+        let mut buf = BytesMut::new();
+
+        codec.encode(b"hello", &mut buf).unwrap();
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(b"hello".to_vec()));
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn decodes_a_zero_length_frame() {
+        let mut codec = LengthPrefixedCodec::new();
+        let mut buf = BytesMut::new();
+
+        codec.encode(b"", &mut buf).unwrap();
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(Vec::new()));
+    }
+
+    #[test]
+    fn returns_none_for_an_incomplete_prefix() {
+        let mut codec = LengthPrefixedCodec::new();
+        // every byte has the continuation bit set, so the prefix never ends
+        let mut buf = BytesMut::from(&[0x80, 0x80, 0x80][..]);
+
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+    }
+
+    #[test]
+    fn returns_none_for_an_incomplete_payload() {
+        let mut codec = LengthPrefixedCodec::new();
+        let mut buf = BytesMut::new();
+        codec.encode(b"hello", &mut buf).unwrap();
+        buf.truncate(buf.len() - 1);
+
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_a_prefix_longer_than_ten_bytes() {
+        let mut codec = LengthPrefixedCodec::new();
+        let mut buf = BytesMut::from(&[0x80; 11][..]);
+
+        assert!(matches!(
+            codec.decode(&mut buf),
+            Err(ChunkCodecError::MaxChunkLengthExceeded)
+        ));
+    }
+
+    #[test]
+    fn rejects_a_length_over_the_configured_max() {
+        let mut codec = LengthPrefixedCodec::new_with_max_length(4);
+        let mut buf = BytesMut::new();
+        codec.encode(b"hello", &mut buf).unwrap();
+
+        assert!(matches!(
+            codec.decode(&mut buf),
+            Err(ChunkCodecError::MaxChunkLengthExceeded)
+        ));
+    }
+}
@@ -0,0 +1,8 @@
+mod chunk_codec;
+pub use chunk_codec::{ChunkCodec, ChunkCodecError};
+
+mod length_prefixed_codec;
+pub use length_prefixed_codec::LengthPrefixedCodec;
+
+mod chunked_transfer_codec;
+pub use chunked_transfer_codec::ChunkedTransferCodec;
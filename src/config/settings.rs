@@ -5,12 +5,78 @@ use serde::Deserialize;
 pub struct Tcp {
     pub endpoint: String,
     pub message_termination_byte: u8,
+    /// When set to a non-empty list, `message_termination_byte` is ignored
+    /// and a chunk boundary is any byte found in this set instead. An empty
+    /// list behaves as if this were unset.
+    #[serde(default)]
+    pub message_termination_bytes: Option<Vec<u8>>,
+    /// When `true`, frames are read as an unsigned varint length prefix
+    /// followed by that many payload bytes, instead of delimiter-based
+    /// chunking. Use this for binary payloads that may legitimately contain
+    /// a delimiter byte.
+    #[serde(default)]
+    pub length_prefixed: bool,
+    /// Maximum accepted frame length when `length_prefixed` is set.
+    #[serde(default)]
+    pub max_frame_length: Option<usize>,
+    /// When `true`, the TCP body is decoded as RFC 7230 chunked
+    /// transfer-encoding, and each decoded chunk is forwarded to ZMQ. Takes
+    /// priority over `length_prefixed`.
+    #[serde(default)]
+    pub chunked_transfer_encoding: bool,
+    /// Maximum accepted chunk size when `chunked_transfer_encoding` is set.
+    #[serde(default)]
+    pub max_chunk_length: Option<usize>,
+    /// TCP `SO_RCVBUF` size, if set.
+    #[serde(default)]
+    pub recv_buffer_size: Option<usize>,
+    /// TCP `SO_SNDBUF` size, if set.
+    #[serde(default)]
+    pub send_buffer_size: Option<usize>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct Zero {
     pub pub_endpoint: String,
     pub pub_topic: String,
+    /// ZMQ `SNDHWM` for the publisher socket, if set.
+    #[serde(default)]
+    pub send_high_water_mark: Option<i32>,
+    /// Capacity of the bounded queue between TCP ingress and the ZMQ
+    /// publisher task.
+    #[serde(default = "default_queue_capacity")]
+    pub queue_capacity: usize,
+    /// What to do when the queue is full.
+    #[serde(default)]
+    pub overload_policy: OverloadPolicy,
+    /// When set, a decoded message of the form `topic<sep>payload` is split
+    /// on the first occurrence of this byte: the left part becomes the ZMQ
+    /// topic frame and the right part the message frame. Messages without
+    /// the separator fall back to `pub_topic`.
+    #[serde(default)]
+    pub topic_separator: Option<u8>,
+}
+
+fn default_queue_capacity() -> usize {
+    1024
+}
+
+/// What the publisher task does when its bounded queue is full.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverloadPolicy {
+    /// Apply backpressure: the producing connection waits for room.
+    Block,
+    /// Drop the oldest queued message to make room for the new one.
+    DropOldest,
+    /// Drop the new message, keeping what's already queued.
+    DropNewest,
+}
+
+impl Default for OverloadPolicy {
+    fn default() -> Self {
+        OverloadPolicy::Block
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -7,17 +7,16 @@ use futures::stream::StreamExt;
 use std::sync::Arc;
 use tokio::net::TcpListener;
 use tokio::net::TcpStream;
-use tokio::sync::Mutex;
 use tokio_util::codec::Decoder;
 
 mod codec;
-use codec::ChunkCodec;
+use codec::{ChunkCodec, ChunkCodecError, ChunkedTransferCodec, LengthPrefixedCodec};
 
 mod config;
 use crate::config::Settings;
 
 mod zero;
-use zero::Broadcast;
+use zero::{spawn_publisher, Broadcast, PublisherHandle, SendStatus};
 
 #[tokio::main]
 async fn main() {
@@ -30,8 +29,22 @@ async fn main() {
     let mut listener = TcpListener::bind(&conf.tcp.endpoint).await.unwrap();
     info!("TCP listener binded at {}", &conf.tcp.endpoint);
 
-    let broadcast = Broadcast::new(&conf.zero.pub_endpoint).unwrap();
-    let broadcast = Arc::new(Mutex::new(broadcast));
+    let mut broadcast =
+        Broadcast::new(&conf.zero.pub_endpoint, conf.zero.send_high_water_mark).unwrap();
+    broadcast.register_hook(|status| {
+        if let SendStatus::Failure { topic, message } = status {
+            warn!(
+                "failed to deliver a {}-byte message on topic \"{}\"",
+                message.len(),
+                topic
+            );
+        }
+    });
+    let publisher = spawn_publisher(
+        broadcast,
+        conf.zero.queue_capacity,
+        conf.zero.overload_policy,
+    );
 
     let settings = Arc::new(conf);
     let server = {
@@ -39,14 +52,14 @@ async fn main() {
             let mut incoming = listener.incoming();
             while let Some(conn) = incoming.next().await {
                 debug!("connection {:?}", conn);
-                let broadcast = Arc::clone(&broadcast);
+                let publisher = publisher.clone();
                 let settings = Arc::clone(&settings);
                 match conn {
                     Err(e) => error!("TCP connection accept failed: {:?}", e),
                     Ok(stream) => {
                         debug!("a TCP client has connected");
                         tokio::spawn(async move {
-                            on_connection(stream, broadcast, settings).await;
+                            on_connection(stream, publisher, settings).await;
                         });
                     }
                 }
@@ -58,20 +71,53 @@ async fn main() {
     server.await;
 }
 
-async fn on_connection(stream: TcpStream, broadcast: Arc<Mutex<Broadcast>>, conf: Arc<Settings>) {
-    let decoder = ChunkCodec::new(conf.tcp.message_termination_byte);
+async fn on_connection(stream: TcpStream, publisher: PublisherHandle, conf: Arc<Settings>) {
+    if let Some(size) = conf.tcp.recv_buffer_size {
+        if let Err(e) = stream.set_recv_buffer_size(size) {
+            error!("failed to set TCP receive buffer size: {}", e);
+        }
+    }
+    if let Some(size) = conf.tcp.send_buffer_size {
+        if let Err(e) = stream.set_send_buffer_size(size) {
+            error!("failed to set TCP send buffer size: {}", e);
+        }
+    }
+
+    if conf.tcp.chunked_transfer_encoding {
+        let decoder = match conf.tcp.max_chunk_length {
+            Some(max_chunk_length) => {
+                ChunkedTransferCodec::new_with_max_chunk_length(max_chunk_length)
+            }
+            None => ChunkedTransferCodec::new(),
+        };
+        forward_binary_frames(decoder, stream, publisher, conf).await;
+    } else if conf.tcp.length_prefixed {
+        let decoder = match conf.tcp.max_frame_length {
+            Some(max_length) => LengthPrefixedCodec::new_with_max_length(max_length),
+            None => LengthPrefixedCodec::new(),
+        };
+        forward_binary_frames(decoder, stream, publisher, conf).await;
+    } else {
+        on_connection_delimited(stream, publisher, conf).await;
+    }
+}
+
+async fn on_connection_delimited(
+    stream: TcpStream,
+    publisher: PublisherHandle,
+    conf: Arc<Settings>,
+) {
+    let decoder = match &conf.tcp.message_termination_bytes {
+        Some(delimiters) if !delimiters.is_empty() => ChunkCodec::new_any(delimiters.clone()),
+        _ => ChunkCodec::new(conf.tcp.message_termination_byte),
+    };
     let mut chunks = decoder.framed(stream);
     while let Some(result) = chunks.next().await {
         match result {
             Ok(message) => {
                 trace!("received TCP message = {:?}", message);
-                broadcast
-                    .lock()
-                    .await
-                    .send(&conf.zero.pub_topic, &message)
-                    .unwrap_or_else(|e| {
-                        error!("An error occurred sending the message {}: {}", message, e)
-                    });
+                let (topic, payload) = route_topic(&conf, message.into_bytes());
+                publisher.publish(topic, payload).await;
             }
             Err(err) => {
                 eprintln!("TCP socket decode error: {:?}", err);
@@ -80,3 +126,42 @@ async fn on_connection(stream: TcpStream, broadcast: Arc<Mutex<Broadcast>>, conf
     }
     debug!("a TCP client closed the connection");
 }
+
+async fn forward_binary_frames<D>(
+    decoder: D,
+    stream: TcpStream,
+    publisher: PublisherHandle,
+    conf: Arc<Settings>,
+) where
+    D: Decoder<Item = Vec<u8>, Error = ChunkCodecError>,
+{
+    let mut frames = decoder.framed(stream);
+    while let Some(result) = frames.next().await {
+        match result {
+            Ok(message) => {
+                trace!("received TCP frame ({} bytes)", message.len());
+                let (topic, payload) = route_topic(&conf, message);
+                publisher.publish(topic, payload).await;
+            }
+            Err(err) => {
+                eprintln!("TCP socket decode error: {:?}", err);
+            }
+        }
+    }
+    debug!("a TCP client closed the connection");
+}
+
+/// Derives the ZMQ topic for a decoded message, splitting it on the
+/// configured `topic_separator` if set and present in the message. Falls
+/// back to `conf.zero.pub_topic` when the separator is unset, absent from
+/// the message, or the would-be topic isn't valid UTF-8.
+fn route_topic(conf: &Settings, message: Vec<u8>) -> (String, Vec<u8>) {
+    if let Some(separator) = conf.zero.topic_separator {
+        if let Some(pos) = message.iter().position(|&b| b == separator) {
+            if let Ok(topic) = std::str::from_utf8(&message[..pos]) {
+                return (topic.to_string(), message[pos + 1..].to_vec());
+            }
+        }
+    }
+    (conf.zero.pub_topic.clone(), message)
+}
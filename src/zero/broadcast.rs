@@ -1,23 +1,96 @@
+use std::sync::Arc;
+
 use zmq::{Context, Error, Socket, PUB};
 
+/// The outcome of a single [`Broadcast::send`] attempt, passed to every
+/// registered [`SendHook`].
+#[derive(Debug, Clone)]
+pub enum SendStatus {
+    /// Both the topic and message frames were written successfully.
+    Success,
+    /// The topic or message frame failed to send, or the message was
+    /// dropped before it ever reached the socket (see the overload policy
+    /// in [`crate::zero::spawn_publisher`]).
+    Failure { topic: String, message: Vec<u8> },
+}
+
+/// An after-send callback. Wrapped in an [`Arc`] so the same registered
+/// hooks can be shared with code that reports messages dropped before they
+/// reach [`Broadcast::send`], such as the publisher's overload policy.
+pub type SendHook = Arc<dyn Fn(SendStatus) + Send + Sync>;
+
+pub(crate) fn notify_hooks(hooks: &[SendHook], status: SendStatus) {
+    for hook in hooks {
+        hook(status.clone());
+    }
+}
+
 pub struct Broadcast {
     socket: Socket,
+    hooks: Vec<SendHook>,
 }
 
 impl Broadcast {
-    pub fn new(endpoint: &str) -> Result<Self, Error> {
+    /// Binds a new `PUB` socket at `endpoint`.
+    ///
+    /// `send_high_water_mark`, if set, bounds the number of outgoing
+    /// messages ZMQ will queue per subscriber before dropping them; see
+    /// `zmq_setsockopt(3)`'s `ZMQ_SNDHWM` for details.
+    pub fn new(endpoint: &str, send_high_water_mark: Option<i32>) -> Result<Self, Error> {
         let context = Context::new();
         let socket = context.socket(PUB)?;
+        if let Some(hwm) = send_high_water_mark {
+            socket.set_sndhwm(hwm)?;
+        }
         socket.bind(endpoint)?;
 
-        Ok(Broadcast { socket })
+        Ok(Broadcast {
+            socket,
+            hooks: Vec::new(),
+        })
+    }
+
+    /// Registers a callback invoked with the outcome of every publish
+    /// attempt. Hooks run in registration order, and are also invoked for
+    /// messages dropped by the publisher's overload policy before they ever
+    /// reach this socket. This is the integration point for emitting
+    /// metrics, writing failed messages to a dead-letter sink, or
+    /// triggering alerts.
+    pub fn register_hook<F>(&mut self, hook: F)
+    where
+        F: Fn(SendStatus) + Send + Sync + 'static,
+    {
+        self.hooks.push(Arc::new(hook));
     }
 
-    pub fn send(&mut self, topic: &str, message: &str) -> Result<(), Error> {
-        trace!("ZMQ sending topic=\"{}\", message: {}", topic, message);
-        self.socket.send(topic, zmq::SNDMORE)?;
-        self.socket.send(message, 0)?;
+    /// Returns the hooks registered so far, for sharing with code that also
+    /// needs to report outcomes on this `Broadcast`'s behalf.
+    pub(crate) fn hooks(&self) -> Vec<SendHook> {
+        self.hooks.clone()
+    }
+
+    pub fn send(&mut self, topic: &str, message: &[u8]) -> Result<(), Error> {
+        trace!(
+            "ZMQ sending topic=\"{}\", message ({} bytes)",
+            topic,
+            message.len()
+        );
+        let result = self
+            .socket
+            .send(topic, zmq::SNDMORE)
+            .and_then(|_| self.socket.send(message, 0));
+
+        match &result {
+            Ok(()) => notify_hooks(&self.hooks, SendStatus::Success),
+            Err(_) => notify_hooks(
+                &self.hooks,
+                SendStatus::Failure {
+                    topic: topic.to_string(),
+                    message: message.to_vec(),
+                },
+            ),
+        }
 
-        Ok(())
+        result
     }
 }
@@ -0,0 +1,7 @@
+mod broadcast;
+pub use broadcast::{Broadcast, SendStatus};
+
+mod queue;
+
+mod publisher;
+pub use publisher::{spawn_publisher, PublisherHandle};
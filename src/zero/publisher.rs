@@ -0,0 +1,106 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::time;
+
+use crate::config::OverloadPolicy;
+
+use super::broadcast::{notify_hooks, Broadcast, SendHook, SendStatus};
+use super::queue::{bounded_queue, QueueSender};
+
+/// How often to log a summary of messages dropped due to overload.
+const DROPPED_MESSAGE_REPORT_INTERVAL: Duration = Duration::from_secs(60);
+
+type QueuedMessage = (String, Vec<u8>);
+
+/// A cheaply cloneable handle producing connections use to publish
+/// messages, without touching the ZMQ socket directly.
+///
+/// Internally this enqueues onto the bounded queue the publisher task owns,
+/// applying the configured [`OverloadPolicy`] if the queue is full.
+#[derive(Clone)]
+pub struct PublisherHandle {
+    sender: QueueSender<QueuedMessage>,
+    policy: OverloadPolicy,
+    dropped: Arc<AtomicU64>,
+    hooks: Arc<Vec<SendHook>>,
+}
+
+impl PublisherHandle {
+    /// Queues `(topic, message)` for publishing.
+    pub async fn publish(&self, topic: String, message: Vec<u8>) {
+        let dropped = match self.policy {
+            OverloadPolicy::Block => {
+                self.sender.send((topic, message)).await;
+                None
+            }
+            OverloadPolicy::DropNewest => self.sender.try_send((topic, message)).await.err(),
+            OverloadPolicy::DropOldest => self.sender.send_evicting_oldest((topic, message)).await,
+        };
+        if let Some((topic, message)) = dropped {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            notify_hooks(&self.hooks, SendStatus::Failure { topic, message });
+        }
+    }
+}
+
+/// Spawns the single task that owns the `Broadcast` ZMQ socket and publishes
+/// every message it receives over a bounded queue, replacing the
+/// `Arc<Mutex<Broadcast>>` every connection used to lock and publish
+/// through directly. Also spawns a task that periodically logs how many
+/// messages were dropped due to overload.
+///
+/// Hooks registered on `broadcast` via [`Broadcast::register_hook`] before
+/// this call also fire for messages the overload policy drops before they
+/// ever reach the socket.
+///
+/// Returns a [`PublisherHandle`] that producing connections clone and use to
+/// enqueue messages.
+pub fn spawn_publisher(
+    broadcast: Broadcast,
+    capacity: usize,
+    policy: OverloadPolicy,
+) -> PublisherHandle {
+    let (sender, mut receiver) = bounded_queue(capacity);
+    let dropped = Arc::new(AtomicU64::new(0));
+    let hooks = Arc::new(broadcast.hooks());
+
+    tokio::spawn({
+        let dropped = Arc::clone(&dropped);
+        async move {
+            let mut interval = time::interval(DROPPED_MESSAGE_REPORT_INTERVAL);
+            loop {
+                interval.tick().await;
+                let count = dropped.swap(0, Ordering::Relaxed);
+                if count > 0 {
+                    warn!(
+                        "dropped {} message(s) in the last {}s due to overload",
+                        count,
+                        DROPPED_MESSAGE_REPORT_INTERVAL.as_secs()
+                    );
+                }
+            }
+        }
+    });
+
+    tokio::spawn(async move {
+        let mut broadcast = broadcast;
+        while let Some((topic, message)) = receiver.recv().await {
+            let message_len = message.len();
+            broadcast.send(&topic, &message).unwrap_or_else(|e| {
+                error!(
+                    "An error occurred publishing a {}-byte message on topic \"{}\": {}",
+                    message_len, topic, e
+                )
+            });
+        }
+    });
+
+    PublisherHandle {
+        sender,
+        policy,
+        dropped,
+        hooks,
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use tokio::sync::{Mutex, Notify};
+
+/// A bounded, multi-producer single-consumer queue.
+///
+/// This is deliberately not a plain [`tokio::sync::mpsc`] channel: the
+/// drop-oldest overload policy needs to evict an already-queued item from
+/// the producer side when the queue is full, which `mpsc` has no way to do
+/// since only the consumer holds the receiving end.
+///
+/// [`tokio::sync::mpsc`]: tokio::sync::mpsc
+struct Shared<T> {
+    capacity: usize,
+    queue: Mutex<VecDeque<T>>,
+    item_ready: Notify,
+    space_ready: Notify,
+}
+
+pub struct QueueSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+pub struct QueueReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+pub fn bounded_queue<T>(capacity: usize) -> (QueueSender<T>, QueueReceiver<T>) {
+    let shared = Arc::new(Shared {
+        capacity,
+        queue: Mutex::new(VecDeque::with_capacity(capacity)),
+        item_ready: Notify::new(),
+        space_ready: Notify::new(),
+    });
+    (
+        QueueSender {
+            shared: Arc::clone(&shared),
+        },
+        QueueReceiver { shared },
+    )
+}
+
+impl<T> Clone for QueueSender<T> {
+    fn clone(&self) -> Self {
+        QueueSender {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T> QueueSender<T> {
+    /// Pushes `item` onto the queue, waiting for room if it's full. This is
+    /// how the `Block` overload policy applies backpressure to the
+    /// producing connection.
+    pub async fn send(&self, item: T) {
+        let mut item = Some(item);
+        loop {
+            let space_ready = self.shared.space_ready.notified();
+            {
+                let mut queue = self.shared.queue.lock().await;
+                if queue.len() < self.shared.capacity {
+                    queue.push_back(item.take().expect("item already sent"));
+                    self.shared.item_ready.notify_one();
+                    return;
+                }
+            }
+            space_ready.await;
+        }
+    }
+
+    /// Pushes `item` only if there's room, handing it back if the queue is
+    /// full. This is how the `DropNewest` overload policy works.
+    pub async fn try_send(&self, item: T) -> Result<(), T> {
+        let mut queue = self.shared.queue.lock().await;
+        if queue.len() < self.shared.capacity {
+            queue.push_back(item);
+            self.shared.item_ready.notify_one();
+            Ok(())
+        } else {
+            Err(item)
+        }
+    }
+
+    /// Pushes `item`, evicting the oldest queued item to make room if the
+    /// queue is full. Returns the evicted item, if any. This is how the
+    /// `DropOldest` overload policy works.
+    pub async fn send_evicting_oldest(&self, item: T) -> Option<T> {
+        let mut queue = self.shared.queue.lock().await;
+        let evicted = if queue.len() >= self.shared.capacity {
+            queue.pop_front()
+        } else {
+            None
+        };
+        queue.push_back(item);
+        self.shared.item_ready.notify_one();
+        evicted
+    }
+}
+
+impl<T> QueueReceiver<T> {
+    /// Pops the next item, waiting if the queue is currently empty. Returns
+    /// `None` once every `QueueSender` has been dropped and the queue has
+    /// been drained.
+    pub async fn recv(&mut self) -> Option<T> {
+        loop {
+            let item_ready = self.shared.item_ready.notified();
+            {
+                let mut queue = self.shared.queue.lock().await;
+                if let Some(item) = queue.pop_front() {
+                    self.shared.space_ready.notify_one();
+                    return Some(item);
+                }
+                if Arc::strong_count(&self.shared) == 1 {
+                    return None;
+                }
+            }
+            item_ready.await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn send_and_recv_round_trip_in_order() {
+        let (sender, mut receiver) = bounded_queue(2);
+        sender.send(1).await;
+        sender.send(2).await;
+
+        assert_eq!(receiver.recv().await, Some(1));
+        assert_eq!(receiver.recv().await, Some(2));
+    }
+
+    #[tokio::test]
+    async fn try_send_rejects_once_the_queue_is_full() {
+        let (sender, _receiver) = bounded_queue(1);
+        assert_eq!(sender.try_send(1).await, Ok(()));
+        assert_eq!(sender.try_send(2).await, Err(2));
+    }
+
+    #[tokio::test]
+    async fn send_evicting_oldest_drops_the_front_item_once_full() {
+        let (sender, mut receiver) = bounded_queue(2);
+        assert_eq!(sender.send_evicting_oldest(1).await, None);
+        assert_eq!(sender.send_evicting_oldest(2).await, None);
+        assert_eq!(sender.send_evicting_oldest(3).await, Some(1));
+
+        assert_eq!(receiver.recv().await, Some(2));
+        assert_eq!(receiver.recv().await, Some(3));
+    }
+
+    #[tokio::test]
+    async fn send_blocks_until_the_receiver_makes_room() {
+        let (sender, mut receiver) = bounded_queue(1);
+        sender.send(1).await;
+
+        let sender2 = sender.clone();
+        let blocked = tokio::spawn(async move {
+            sender2.send(2).await;
+        });
+
+        // give the blocked send a chance to register its waiter before we
+        // free up space; this is inherently a best-effort wait, but the
+        // subsequent recv()s below are what actually prove correctness.
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!blocked.is_finished());
+
+        assert_eq!(receiver.recv().await, Some(1));
+        blocked.await.unwrap();
+        assert_eq!(receiver.recv().await, Some(2));
+    }
+
+    #[tokio::test]
+    async fn recv_returns_none_once_every_sender_is_dropped() {
+        let (sender, mut receiver) = bounded_queue::<i32>(1);
+        drop(sender);
+
+        assert_eq!(receiver.recv().await, None);
+    }
+
+    #[tokio::test]
+    async fn multiple_producers_wake_the_receiver() {
+        let (sender, mut receiver) = bounded_queue(4);
+        let senders: Vec<_> = (0..4).map(|_| sender.clone()).collect();
+        drop(sender);
+
+        let mut tasks = Vec::new();
+        for (i, sender) in senders.into_iter().enumerate() {
+            tasks.push(tokio::spawn(async move {
+                sender.send(i).await;
+            }));
+        }
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        let mut received = Vec::new();
+        while let Some(item) = receiver.recv().await {
+            received.push(item);
+        }
+        received.sort_unstable();
+        assert_eq!(received, vec![0, 1, 2, 3]);
+    }
+}